@@ -23,10 +23,182 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 #[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
-use parity_scale_codec::{Decode, Encode};
-use scale_info::TypeInfo;
-use serde::{Deserialize, Serialize};
+use core::iter;
+use parity_scale_codec::{Decode, Encode, Input, Output};
+use scale_info::build::Fields;
+use scale_info::{Path, Type, TypeInfo};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A variable-length integer encoding of a `u64`, following the scheme used by QUIC (and
+/// `mls-rs-codec`'s `varint`): the two most significant bits of the first byte select the
+/// total encoded length (`00` => 1 byte/6-bit value, `01` => 2 bytes/14-bit value, `10` => 4
+/// bytes/30-bit value, `11` => 8 bytes/62-bit value), with the remaining bits holding the
+/// big-endian value. Encoding always picks the shortest length that fits the value, so values
+/// up to `2^62 - 1` can be represented without the fixed-width ceiling of a packed byte array.
+///
+/// **Metadata caveat:** scale-info's `Type` system has no way to describe this variable-length
+/// format precisely, so [`Self`]'s `TypeInfo` impl advertises it as an opaque byte sequence
+/// rather than as a fixed-size integer. A generic, metadata-driven SCALE decoder (e.g.
+/// polkadot.js, subxt) that does not special-case this type will not decode it correctly; only
+/// the `Encode`/`Decode` impls here understand the format.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VarInt(pub u64);
+
+impl VarInt {
+    const MAX_1_BYTE: u64 = (1 << 6) - 1;
+    const MAX_2_BYTE: u64 = (1 << 14) - 1;
+    const MAX_4_BYTE: u64 = (1 << 30) - 1;
+    /// Largest value representable by the 8-byte form (62-bit value, the remaining 2 bits are
+    /// the length tag)
+    const MAX_8_BYTE: u64 = (1 << 62) - 1;
+
+    /// Value as `u64`
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<VarInt> for u64 {
+    fn from(value: VarInt) -> Self {
+        value.0
+    }
+}
+
+impl From<u64> for VarInt {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl Encode for VarInt {
+    fn size_hint(&self) -> usize {
+        match self.0 {
+            0..=Self::MAX_1_BYTE => 1,
+            0..=Self::MAX_2_BYTE => 2,
+            0..=Self::MAX_4_BYTE => 4,
+            _ => 8,
+        }
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        match self.0 {
+            0..=Self::MAX_1_BYTE => {
+                dest.push_byte(self.0 as u8);
+            }
+            0..=Self::MAX_2_BYTE => {
+                let value = 0b01 << 14 | self.0 as u16;
+                dest.write(&value.to_be_bytes());
+            }
+            0..=Self::MAX_4_BYTE => {
+                let value = 0b10 << 30 | self.0 as u32;
+                dest.write(&value.to_be_bytes());
+            }
+            _ => {
+                debug_assert!(
+                    self.0 <= Self::MAX_8_BYTE,
+                    "VarInt only supports values up to 2^62 - 1"
+                );
+                // Values beyond 2^62 - 1 would have their top bits OR-ed into the length tag,
+                // silently corrupting the encoding; clamp so release builds fail safe (a
+                // decodable, if saturated, value) instead of corrupting the wire format.
+                let value = 0b11 << 62 | self.0.min(Self::MAX_8_BYTE);
+                dest.write(&value.to_be_bytes());
+            }
+        }
+    }
+}
+
+impl Decode for VarInt {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let first_byte = input.read_byte()?;
+        let value = match first_byte >> 6 {
+            0b00 => u64::from(first_byte & 0b0011_1111),
+            0b01 => {
+                let mut rest = [0u8; 1];
+                input.read(&mut rest)?;
+                let value = u16::from_be_bytes([first_byte & 0b0011_1111, rest[0]]);
+                u64::from(value)
+            }
+            0b10 => {
+                let mut rest = [0u8; 3];
+                input.read(&mut rest)?;
+                let value =
+                    u32::from_be_bytes([first_byte & 0b0011_1111, rest[0], rest[1], rest[2]]);
+                u64::from(value)
+            }
+            _ => {
+                let mut rest = [0u8; 7];
+                input.read(&mut rest)?;
+                let mut bytes = [0u8; 8];
+                bytes[0] = first_byte & 0b0011_1111;
+                bytes[1..].copy_from_slice(&rest);
+                u64::from_be_bytes(bytes)
+            }
+        };
+
+        Ok(VarInt(value))
+    }
+}
+
+impl TypeInfo for VarInt {
+    type Identity = Self;
+
+    /// **Metadata caveat:** `Encode`/`Decode` above emit a non-standard, variable-length byte
+    /// sequence (1, 2, 4, or 8 bytes chosen by value), which scale-info's `Type` system has no
+    /// way to describe precisely. We advertise the field as an opaque byte sequence rather than
+    /// (incorrectly) as a fixed-size `u64`, but metadata-driven decoders (e.g. polkadot.js,
+    /// subxt) that don't special-case the `VarInt` path will still fail to decode it correctly;
+    /// only the Rust `Encode`/`Decode` impls on this type understand the format.
+    fn type_info() -> Type {
+        Type::builder()
+            .path(Path::new("VarInt", module_path!()))
+            .docs(&[
+                "Variable-length integer (QUIC-style varint); NOT decodable from this metadata \
+                 alone, see `VarInt`'s Rust docs",
+            ])
+            .composite(Fields::unnamed().field(|f| f.ty::<Vec<u8>>()))
+    }
+}
+
+/// Serializes/deserializes a packed 24-bit little-endian `[u8; 3]` field as a `0x`-prefixed hex
+/// string for serde (JSON/RPC consumers), while SCALE `Encode`/`Decode` (derived on the
+/// containing type) keeps using the compact 3-byte layout untouched.
+mod hex_u24 {
+    #[cfg(not(feature = "std"))]
+    use super::{format, String};
+    use super::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub(super) fn serialize<S>(bytes: &[u8; 3], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+        serializer.serialize_str(&format!("{value:#08x}"))
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<[u8; 3], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let value = u32::from_str_radix(s.trim_start_matches("0x"), 16)
+            .map_err(|error| D::Error::custom(format!("invalid hex value {s}: {error}")))?;
+        if value > 0x00ff_ffff {
+            return Err(D::Error::custom(format!(
+                "value {value:#x} does not fit in 24 bits"
+            )));
+        }
+        let bytes = value.to_le_bytes();
+        Ok([bytes[0], bytes[1], bytes[2]])
+    }
+}
 
 /// Object stored inside of the block
 #[derive(
@@ -50,26 +222,41 @@ pub enum BlockObject {
     #[codec(index = 0)]
     V0 {
         /// 24-bit little-endian offset of the object
+        #[serde(with = "hex_u24")]
         offset: [u8; 3],
         /// 24-bit little-endian size of the object
+        #[serde(with = "hex_u24")]
         size: [u8; 3],
     },
+    /// V1 of object mapping data structure, using variable-length integers for `offset` and
+    /// `size` so objects larger than the 16 MiB ceiling of `V0` can be mapped
+    #[codec(index = 1)]
+    V1 {
+        /// Variable-length offset of the object
+        offset: VarInt,
+        /// Variable-length size of the object
+        size: VarInt,
+    },
 }
 
 impl BlockObject {
-    /// Offset of the object (limited to 24-bit size internally)
-    pub fn offset(&self) -> u32 {
+    /// Offset of the object
+    pub fn offset(&self) -> u64 {
         match self {
             BlockObject::V0 { offset, .. } => {
-                u32::from_le_bytes([offset[0], offset[1], offset[2], 0])
+                u64::from(u32::from_le_bytes([offset[0], offset[1], offset[2], 0]))
             }
+            BlockObject::V1 { offset, .. } => offset.get(),
         }
     }
 
-    /// Offset of the object (limited to 24-bit size internally)
-    pub fn size(&self) -> u32 {
+    /// Size of the object
+    pub fn size(&self) -> u64 {
         match self {
-            BlockObject::V0 { size, .. } => u32::from_le_bytes([size[0], size[1], size[2], 0]),
+            BlockObject::V0 { size, .. } => {
+                u64::from(u32::from_le_bytes([size[0], size[1], size[2], 0]))
+            }
+            BlockObject::V1 { size, .. } => size.get(),
         }
     }
 }
@@ -119,22 +306,36 @@ pub enum PieceObject {
         /// Offset of the object
         offset: u16,
         /// 24-bit little-endian size of the object
+        #[serde(with = "hex_u24")]
         size: [u8; 3],
     },
+    /// V1 of object mapping data structure, using variable-length integers for `offset` and
+    /// `size` so objects larger than the 16 MiB ceiling of `V0` can be mapped
+    #[codec(index = 1)]
+    V1 {
+        /// Variable-length offset of the object
+        offset: VarInt,
+        /// Variable-length size of the object
+        size: VarInt,
+    },
 }
 
 impl PieceObject {
     /// Offset of the object
-    pub fn offset(&self) -> u16 {
+    pub fn offset(&self) -> u64 {
         match self {
-            PieceObject::V0 { offset, .. } => *offset,
+            PieceObject::V0 { offset, .. } => u64::from(*offset),
+            PieceObject::V1 { offset, .. } => offset.get(),
         }
     }
 
-    /// Offset of the object (limited to 24-bit size internally)
-    pub fn size(&self) -> u32 {
+    /// Size of the object
+    pub fn size(&self) -> u64 {
         match self {
-            PieceObject::V0 { size, .. } => u32::from_le_bytes([size[0], size[1], size[2], 0]),
+            PieceObject::V0 { size, .. } => {
+                u64::from(u32::from_le_bytes([size[0], size[1], size[2], 0]))
+            }
+            PieceObject::V1 { size, .. } => size.get(),
         }
     }
 }
@@ -186,29 +387,409 @@ pub enum GlobalObject {
         /// Offset of the object
         offset: u16,
         /// 24-bit little-endian size of the object
+        #[serde(with = "hex_u24")]
         size: [u8; 3],
     },
+    /// V1 of object mapping data structure, using variable-length integers for `offset` and
+    /// `size` so objects larger than the 16 MiB ceiling of `V0` can be mapped
+    #[codec(index = 1)]
+    V1 {
+        /// Piece index where object is contained (at least its beginning, might not fit fully)
+        piece_index: u64,
+        /// Variable-length offset of the object
+        offset: VarInt,
+        /// Variable-length size of the object
+        size: VarInt,
+    },
+    /// V2 of object mapping data structure, adding an optional content hash that retrieval code
+    /// can use to verify the reconstructed bytes without re-deriving the mapping from chain
+    /// history
+    #[codec(index = 2)]
+    V2 {
+        /// Piece index where object is contained (at least its beginning, might not fit fully)
+        piece_index: u64,
+        /// Variable-length offset of the object
+        offset: VarInt,
+        /// Variable-length size of the object
+        size: VarInt,
+        /// Content hash of the object, if known
+        hash: Option<ObjectHash>,
+    },
 }
 
 impl GlobalObject {
     /// Piece index where object is contained (at least its beginning, might not fit fully)
     pub fn piece_index(&self) -> u64 {
         match self {
-            GlobalObject::V0 { piece_index, .. } => *piece_index,
+            GlobalObject::V0 { piece_index, .. }
+            | GlobalObject::V1 { piece_index, .. }
+            | GlobalObject::V2 { piece_index, .. } => *piece_index,
         }
     }
 
     /// Offset of the object
-    pub fn offset(&self) -> u16 {
+    pub fn offset(&self) -> u64 {
+        match self {
+            GlobalObject::V0 { offset, .. } => u64::from(*offset),
+            GlobalObject::V1 { offset, .. } | GlobalObject::V2 { offset, .. } => offset.get(),
+        }
+    }
+
+    /// Size of the object
+    pub fn size(&self) -> u64 {
         match self {
-            GlobalObject::V0 { offset, .. } => *offset,
+            GlobalObject::V0 { size, .. } => {
+                u64::from(u32::from_le_bytes([size[0], size[1], size[2], 0]))
+            }
+            GlobalObject::V1 { size, .. } | GlobalObject::V2 { size, .. } => size.get(),
         }
     }
 
-    /// Offset of the object (limited to 24-bit size internally)
-    pub fn size(&self) -> u32 {
+    /// Content hash of the object, if present, for retrieval integrity verification
+    pub fn hash(&self) -> Option<&[u8; 32]> {
         match self {
-            GlobalObject::V0 { size, .. } => u32::from_le_bytes([size[0], size[1], size[2], 0]),
+            GlobalObject::V0 { .. } | GlobalObject::V1 { .. } => None,
+            GlobalObject::V2 { hash, .. } => hash.as_ref().map(ObjectHash::as_bytes),
+        }
+    }
+
+    /// Verify that `data` matches the stored content hash.
+    ///
+    /// Returns `true` when no hash is present, since there is nothing to verify against.
+    ///
+    /// Only available with the `std` feature: computing the hash pulls in the `blake3`
+    /// dependency, and this is retrieval-side (node) logic that never needs to run in the
+    /// `no_std` runtime.
+    #[cfg(feature = "std")]
+    pub fn verify(&self, data: &[u8]) -> bool {
+        match self.hash() {
+            Some(hash) => *hash == *ObjectHash::new(data).as_bytes(),
+            None => true,
+        }
+    }
+
+    /// Piece indices (starting at [`Self::piece_index`]) that this object's bytes occupy, given
+    /// `piece_size` is the number of object-mapping-relevant bytes available in each piece.
+    ///
+    /// Panics if `piece_size` is `0`.
+    pub fn spanned_piece_indices(&self, piece_size: u32) -> impl Iterator<Item = u64> {
+        assert_ne!(piece_size, 0, "piece_size must not be 0");
+
+        let piece_index = self.piece_index();
+
+        piece_index..=(piece_index + self.last_spanned_piece_offset(piece_size))
+    }
+
+    /// Whether the object fits entirely within the piece it starts in.
+    ///
+    /// Panics if `piece_size` is `0`.
+    pub fn fits_in_single_piece(&self, piece_size: u32) -> bool {
+        assert_ne!(piece_size, 0, "piece_size must not be 0");
+
+        self.last_spanned_piece_offset(piece_size) == 0
+    }
+
+    /// Number of pieces (minus one) past [`Self::piece_index`] that this object's bytes reach
+    fn last_spanned_piece_offset(&self, piece_size: u32) -> u64 {
+        let piece_size = u64::from(piece_size);
+        let last_byte_offset = self.offset() + self.size().saturating_sub(1);
+
+        last_byte_offset / piece_size
+    }
+}
+
+/// Opaque 32-byte content hash of an object, used by [`GlobalObject::verify`] to detect
+/// corruption or incorrect offsets in a retrieved object without re-deriving the mapping
+///
+/// The concrete hashing algorithm is an implementation detail; callers should not assume
+/// anything about it beyond its fixed 32-byte output.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Encode,
+    Decode,
+    TypeInfo,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ObjectHash([u8; 32]);
+
+impl ObjectHash {
+    /// Compute the content hash of `data`, using the same `blake3` hash used elsewhere in the
+    /// crate for piece and record hashing.
+    ///
+    /// Requires the `blake3` dependency (`default-features = false` for `no_std` compatibility),
+    /// so this constructor is only available with the `std` feature; see [`Self::as_bytes`] for
+    /// the always-available, algorithm-agnostic accessor.
+    #[cfg(feature = "std")]
+    pub fn new(data: &[u8]) -> Self {
+        Self(*blake3::hash(data).as_bytes())
+    }
+
+    /// Hash as raw bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Mapping of global objects in the global history of the blockchain
+///
+/// Unlike [`BlockObjectMapping`] and [`PieceObjectMapping`], `objects` is kept private: entries
+/// are always maintained in sorted order of `(piece_index, offset)` so that [`Self::objects_in_piece`]
+/// can binary-search for its piece and [`Self::by_piece_index`] can group objects in a single
+/// pass without sorting. `Decode` and `Deserialize`
+/// are implemented by hand below rather than derived so that a mapping coming from untrusted
+/// wire input (SCALE or JSON) is always re-sorted into that invariant rather than trusted as-is.
+#[derive(Default, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, TypeInfo, Serialize)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct GlobalObjectMapping {
+    objects: Vec<GlobalObject>,
+}
+
+impl Decode for GlobalObjectMapping {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let objects = Vec::<GlobalObject>::decode(input)?;
+
+        Ok(Self::from_iter(objects))
+    }
+}
+
+impl<'de> Deserialize<'de> for GlobalObjectMapping {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct GlobalObjectMappingRepr {
+            objects: Vec<GlobalObject>,
         }
+
+        let repr = GlobalObjectMappingRepr::deserialize(deserializer)?;
+
+        Ok(Self::from_iter(repr.objects))
+    }
+}
+
+impl GlobalObjectMapping {
+    /// Create an empty mapping
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Global objects contained in the mapping, sorted by `(piece_index, offset)`
+    pub fn objects(&self) -> &[GlobalObject] {
+        &self.objects
+    }
+
+    /// Insert an object into the mapping, keeping entries sorted by `(piece_index, offset)`
+    pub fn insert(&mut self, object: GlobalObject) {
+        let key = (object.piece_index(), object.offset());
+        let position = self
+            .objects
+            .partition_point(|existing| (existing.piece_index(), existing.offset()) <= key);
+        self.objects.insert(position, object);
+    }
+
+    /// Objects whose beginning is contained in `piece_index`
+    pub fn objects_in_piece(&self, piece_index: u64) -> impl Iterator<Item = &GlobalObject> {
+        let start = self
+            .objects
+            .partition_point(|object| object.piece_index() < piece_index);
+        self.objects[start..]
+            .iter()
+            .take_while(move |object| object.piece_index() == piece_index)
+    }
+
+    /// Objects grouped by `piece_index`, in ascending order, for efficient range queries
+    pub fn by_piece_index(&self) -> impl Iterator<Item = (u64, &[GlobalObject])> {
+        let mut remaining = self.objects.as_slice();
+
+        iter::from_fn(move || {
+            let piece_index = remaining.first()?.piece_index();
+            let split_at = remaining
+                .iter()
+                .position(|object| object.piece_index() != piece_index)
+                .unwrap_or(remaining.len());
+            let (group, rest) = remaining.split_at(split_at);
+            remaining = rest;
+
+            Some((piece_index, group))
+        })
     }
-}
\ No newline at end of file
+}
+
+impl FromIterator<GlobalObject> for GlobalObjectMapping {
+    /// Builds the mapping by sorting all objects at once, rather than inserting one at a time,
+    /// so bulk construction (e.g. decoding untrusted wire/JSON input) is `O(n log n)` rather
+    /// than the `O(n^2)` that repeated [`Self::insert`] would incur.
+    fn from_iter<T: IntoIterator<Item = GlobalObject>>(iter: T) -> Self {
+        let mut objects = Vec::from_iter(iter);
+        objects.sort_unstable_by_key(|object| (object.piece_index(), object.offset()));
+
+        Self { objects }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_round_trips_at_tag_boundaries() {
+        for value in [
+            0,
+            VarInt::MAX_1_BYTE,
+            VarInt::MAX_1_BYTE + 1,
+            VarInt::MAX_2_BYTE,
+            VarInt::MAX_2_BYTE + 1,
+            VarInt::MAX_4_BYTE,
+            VarInt::MAX_4_BYTE + 1,
+            VarInt::MAX_8_BYTE,
+        ] {
+            let encoded = VarInt(value).encode();
+            let decoded = VarInt::decode(&mut encoded.as_slice()).unwrap();
+            assert_eq!(decoded.get(), value, "value {value} did not round-trip");
+        }
+    }
+
+    #[test]
+    fn varint_picks_shortest_encoding() {
+        assert_eq!(VarInt(VarInt::MAX_1_BYTE).encode().len(), 1);
+        assert_eq!(VarInt(VarInt::MAX_1_BYTE + 1).encode().len(), 2);
+        assert_eq!(VarInt(VarInt::MAX_2_BYTE).encode().len(), 2);
+        assert_eq!(VarInt(VarInt::MAX_2_BYTE + 1).encode().len(), 4);
+        assert_eq!(VarInt(VarInt::MAX_4_BYTE).encode().len(), 4);
+        assert_eq!(VarInt(VarInt::MAX_4_BYTE + 1).encode().len(), 8);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "VarInt only supports values up to 2^62 - 1")]
+    fn varint_rejects_values_beyond_62_bits() {
+        let _ = VarInt(VarInt::MAX_8_BYTE + 1).encode();
+    }
+
+    #[test]
+    fn hex_u24_round_trips_through_json() {
+        let object = BlockObject::V0 {
+            offset: [0x34, 0x12, 0x00],
+            size: [0xff, 0xff, 0xff],
+        };
+
+        let json = serde_json::to_value(&object).unwrap();
+        assert_eq!(json["v0"]["offset"], "0x001234");
+        assert_eq!(json["v0"]["size"], "0xffffff");
+
+        let decoded: BlockObject = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, object);
+    }
+
+    #[test]
+    fn hex_u24_rejects_out_of_range_values() {
+        let json = serde_json::json!({ "v0": { "offset": "0x1000000", "size": "0x0" } });
+        assert!(serde_json::from_value::<BlockObject>(json).is_err());
+    }
+
+    fn global_object(piece_index: u64, offset: u64) -> GlobalObject {
+        GlobalObject::V1 {
+            piece_index,
+            offset: VarInt(offset),
+            size: VarInt(1),
+        }
+    }
+
+    #[test]
+    fn global_object_mapping_insert_keeps_sort_order() {
+        let mut mapping = GlobalObjectMapping::new();
+        for object in [
+            global_object(2, 5),
+            global_object(1, 10),
+            global_object(1, 0),
+            global_object(2, 0),
+        ] {
+            mapping.insert(object);
+        }
+
+        let piece_indices_and_offsets = mapping
+            .objects()
+            .iter()
+            .map(|object| (object.piece_index(), object.offset()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            piece_indices_and_offsets,
+            vec![(1, 0), (1, 10), (2, 0), (2, 5)]
+        );
+
+        let grouped = mapping.by_piece_index().collect::<Vec<_>>();
+        assert_eq!(grouped[0].0, 1);
+        assert_eq!(grouped[0].1.len(), 2);
+        assert_eq!(grouped[1].0, 2);
+        assert_eq!(grouped[1].1.len(), 2);
+
+        assert_eq!(mapping.objects_in_piece(1).count(), 2);
+        assert_eq!(mapping.objects_in_piece(3).count(), 0);
+    }
+
+    #[test]
+    fn global_object_mapping_decode_sorts_unordered_wire_input() {
+        let unordered = vec![global_object(2, 0), global_object(1, 0)];
+        let encoded = unordered.encode();
+
+        let mapping = GlobalObjectMapping::decode(&mut encoded.as_slice()).unwrap();
+        let piece_indices = mapping
+            .objects()
+            .iter()
+            .map(GlobalObject::piece_index)
+            .collect::<Vec<_>>();
+        assert_eq!(piece_indices, vec![1, 2]);
+    }
+
+    #[test]
+    fn spanned_piece_indices_handles_boundaries() {
+        let piece_size = 4096u32;
+
+        let fits_exactly = GlobalObject::V1 {
+            piece_index: 0,
+            offset: VarInt(0),
+            size: VarInt(u64::from(piece_size)),
+        };
+        assert!(fits_exactly.fits_in_single_piece(piece_size));
+
+        let at_piece_edge = GlobalObject::V1 {
+            piece_index: 0,
+            offset: VarInt(0),
+            size: VarInt(u64::from(piece_size) + 1),
+        };
+        assert!(!at_piece_edge.fits_in_single_piece(piece_size));
+        assert_eq!(
+            at_piece_edge
+                .spanned_piece_indices(piece_size)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        let crosses_many_pieces = GlobalObject::V1 {
+            piece_index: 5,
+            offset: VarInt(u64::from(piece_size) - 1),
+            size: VarInt(u64::from(piece_size) * 3),
+        };
+        assert_eq!(
+            crosses_many_pieces
+                .spanned_piece_indices(piece_size)
+                .collect::<Vec<_>>(),
+            vec![5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "piece_size must not be 0")]
+    fn spanned_piece_indices_panics_on_zero_piece_size() {
+        let _ = global_object(0, 0).spanned_piece_indices(0);
+    }
+}